@@ -10,21 +10,712 @@ pub extern "C" fn alloc(len: usize) -> *mut u8 {
     ptr
 }
 
+/// The wire schema version this guest understands. Hosts send this back
+/// in every request envelope so the guest can refuse payloads shaped for
+/// a schema it doesn't speak instead of silently misinterpreting them.
+pub const SCHEMA_VERSION: &str = "1.0";
+
+/// Set in the low 32 bits of `handle`'s return value when the response
+/// body is a decode/parse or oversized-response diagnostic. Envelope-level
+/// errors (schema mismatch, unknown method) are returned with it clear.
+/// See `handle` for the full layout.
+const STATUS_ERROR_BIT: u64 = 1 << 31;
+
+/// # Guest/host ABI contract
+///
+/// `handle` packs its response into a single `u64`:
+///
+/// - bits 63..32: `out_ptr`, the guest memory address of the response body
+/// - bit 31 (`STATUS_ERROR_BIT`): 1 if the body is a decode/parse
+///   diagnostic or a "response too large" diagnostic, 0 otherwise (an
+///   error envelope such as a schema mismatch is sent with it clear)
+/// - bits 30..0: `out_len`, the length of the response body in bytes
+///   (31 bits, so bodies are capped at 2 GiB)
+///
+/// Hosts should mask off bit 31 to recover `out_len` and check it before
+/// trusting the body as a "real" result rather than a diagnostic. `alloc`
+/// itself is unchanged.
 #[no_mangle]
 pub unsafe extern "C" fn handle(ptr: *mut u8, len: usize) -> u64 {
     let input_slice = slice::from_raw_parts(ptr, len);
-    let input_str = str::from_utf8(input_slice).unwrap_or("{}");
-    
-    // Simple logic: echo input with a greeting
-    // In a real application, you would parse the JSON input.
-    // Here we just construct a JSON-like string manually.
-    
-    let output = format!(r#"{{\"message\": \"Hello from Rust WASM!\", \"input\": \"{}\"}}"#, input_str.replace("\"", "\\\""));
-    let output_bytes = output.as_bytes();
-    let out_len = output_bytes.len();
-    let out_ptr = alloc(out_len);
-    
-    std::ptr::copy_nonoverlapping(output_bytes.as_ptr(), out_ptr, out_len);
-    
-    ((out_ptr as u64) << 32) | (out_len as u64)
+
+    let input_str = match str::from_utf8(input_slice) {
+        Ok(s) => s,
+        Err(e) => return emit(&decode_diagnostic("invalid UTF-8 input", e.valid_up_to()), true),
+    };
+
+    let value = match json::parse(input_str) {
+        Ok(v) => v,
+        Err(e) => return emit(&decode_diagnostic(&e.message, e.offset), true),
+    };
+
+    let response = handle_envelope(value);
+    emit(&json::to_string(&response), false)
+}
+
+fn decode_diagnostic(reason: &str, offset: usize) -> String {
+    let mut obj = json::Map::new();
+    obj.insert("error", json::Value::String("decode failed".to_string()));
+    obj.insert("offset", json::Value::Number(offset as f64));
+    obj.insert("reason", json::Value::String(reason.to_string()));
+    json::to_string(&json::Value::Object(obj))
+}
+
+/// The largest body `out_len`'s 31 bits can represent without colliding
+/// with `STATUS_ERROR_BIT`.
+const MAX_BODY_LEN: usize = STATUS_ERROR_BIT as usize - 1;
+
+unsafe fn emit(body: &str, is_error: bool) -> u64 {
+    if body.len() > MAX_BODY_LEN {
+        let diagnostic = oversized_diagnostic(body.len());
+        return write_response(&diagnostic, true);
+    }
+    write_response(body, is_error)
+}
+
+fn oversized_diagnostic(len: usize) -> String {
+    let mut obj = json::Map::new();
+    obj.insert("error", json::Value::String("response too large".to_string()));
+    obj.insert("len", json::Value::Number(len as f64));
+    obj.insert("max", json::Value::Number(MAX_BODY_LEN as f64));
+    json::to_string(&json::Value::Object(obj))
+}
+
+unsafe fn write_response(body: &str, is_error: bool) -> u64 {
+    let body_bytes = body.as_bytes();
+    let out_len = body_bytes.len() as u64;
+    let out_ptr = alloc(body_bytes.len());
+
+    std::ptr::copy_nonoverlapping(body_bytes.as_ptr(), out_ptr, body_bytes.len());
+
+    let mut packed = (out_ptr as u64) << 32;
+    packed |= out_len & !STATUS_ERROR_BIT;
+    if is_error {
+        packed |= STATUS_ERROR_BIT;
+    }
+    packed
+}
+
+/// Validates the `{"schema": ..., "payload": ...}` envelope and, once the
+/// schema matches, runs the actual request logic over `payload`.
+fn handle_envelope(value: json::Value) -> json::Value {
+    let obj = match value {
+        json::Value::Object(map) => map,
+        _ => return error_envelope("request must be a JSON object"),
+    };
+
+    let schema = match obj.get("schema") {
+        Some(json::Value::String(s)) => s.as_str(),
+        None => return schema_mismatch("<missing>"),
+        Some(_) => return schema_mismatch("<non-string>"),
+    };
+    if schema != SCHEMA_VERSION {
+        return schema_mismatch(schema);
+    }
+
+    let mut payload = match obj.get("payload") {
+        Some(json::Value::Object(payload)) => payload.clone(),
+        _ => return error_envelope("missing \"payload\" object"),
+    };
+
+    let method = match payload.remove("method") {
+        Some(json::Value::String(method)) => method,
+        _ => return error_envelope("missing \"method\" string in payload"),
+    };
+
+    let result = dispatch(&method, json::Value::Object(payload));
+
+    let mut response = json::Map::new();
+    response.insert("schema", json::Value::String(SCHEMA_VERSION.to_string()));
+    response.insert("payload", result);
+    json::Value::Object(response)
+}
+
+/// Routes `method` to its registered handler, or an error envelope if no
+/// handler was registered under that name. See `handlers::HANDLERS`.
+fn dispatch(method: &str, payload: json::Value) -> json::Value {
+    match handlers::HANDLERS.iter().find(|(name, _)| *name == method) {
+        Some((_, handler)) => handler(payload),
+        None => error_envelope(&format!("unknown method: {}", method)),
+    }
+}
+
+fn schema_mismatch(found: &str) -> json::Value {
+    let mut obj = json::Map::new();
+    obj.insert("error", json::Value::String("schema mismatch".to_string()));
+    obj.insert("expected", json::Value::String(SCHEMA_VERSION.to_string()));
+    obj.insert("found", json::Value::String(found.to_string()));
+    json::Value::Object(obj)
+}
+
+fn error_envelope(message: &str) -> json::Value {
+    let mut obj = json::Map::new();
+    obj.insert("error", json::Value::String(message.to_string()));
+    json::Value::Object(obj)
+}
+
+/// A small, dependency-free JSON value type, parser and serializer.
+///
+/// `serde_json` (or any other crate) would normally do this, but this
+/// module is compiled to WASM and is meant to stay tiny, so we roll our
+/// own untyped tree instead of pulling in a dependency.
+mod json {
+    /// An untyped JSON value.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Value {
+        Null,
+        Bool(bool),
+        Number(f64),
+        String(String),
+        Array(Vec<Value>),
+        Object(Map),
+    }
+
+    /// An insertion-ordered string-keyed map.
+    ///
+    /// JSON objects don't have a defined order, but round-tripping through
+    /// a `HashMap` would still shuffle keys on every call, which makes
+    /// output hard to read and diff. Plain `Vec<(String, Value)>` keeps
+    /// insertion order cheaply for the small objects this module deals with.
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct Map {
+        entries: Vec<(String, Value)>,
+    }
+
+    impl Map {
+        pub fn new() -> Self {
+            Map { entries: Vec::new() }
+        }
+
+        pub fn get(&self, key: &str) -> Option<&Value> {
+            self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+        }
+
+        pub fn insert(&mut self, key: impl Into<String>, value: Value) {
+            let key = key.into();
+            match self.entries.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => entry.1 = value,
+                None => self.entries.push((key, value)),
+            }
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = &(String, Value)> {
+            self.entries.iter()
+        }
+
+        pub fn remove(&mut self, key: &str) -> Option<Value> {
+            let index = self.entries.iter().position(|(k, _)| k == key)?;
+            Some(self.entries.remove(index).1)
+        }
+    }
+
+    /// An error produced while parsing JSON text, with the byte offset of
+    /// the character that caused it.
+    #[derive(Debug, Clone)]
+    pub struct ParseError {
+        pub offset: usize,
+        pub message: String,
+    }
+
+    pub fn parse(input: &str) -> Result<Value, ParseError> {
+        let mut parser = Parser { bytes: input.as_bytes(), pos: 0 };
+        parser.skip_whitespace();
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(parser.error("trailing characters after JSON value"));
+        }
+        Ok(value)
+    }
+
+    struct Parser<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        fn error(&self, message: &str) -> ParseError {
+            ParseError { offset: self.pos, message: message.to_string() }
+        }
+
+        fn peek(&self) -> Option<u8> {
+            self.bytes.get(self.pos).copied()
+        }
+
+        fn bump(&mut self) -> Option<u8> {
+            let b = self.peek();
+            if b.is_some() {
+                self.pos += 1;
+            }
+            b
+        }
+
+        fn skip_whitespace(&mut self) {
+            while let Some(b) = self.peek() {
+                match b {
+                    b' ' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                    _ => break,
+                }
+            }
+        }
+
+        fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+            if self.peek() == Some(byte) {
+                self.pos += 1;
+                Ok(())
+            } else {
+                Err(self.error(&format!("expected '{}'", byte as char)))
+            }
+        }
+
+        fn expect_literal(&mut self, literal: &str) -> Result<(), ParseError> {
+            for expected in literal.bytes() {
+                if self.bump() != Some(expected) {
+                    return Err(self.error(&format!("expected literal '{}'", literal)));
+                }
+            }
+            Ok(())
+        }
+
+        fn parse_value(&mut self) -> Result<Value, ParseError> {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'{') => self.parse_object(),
+                Some(b'[') => self.parse_array(),
+                Some(b'"') => self.parse_string().map(Value::String),
+                Some(b't') => {
+                    self.expect_literal("true")?;
+                    Ok(Value::Bool(true))
+                }
+                Some(b'f') => {
+                    self.expect_literal("false")?;
+                    Ok(Value::Bool(false))
+                }
+                Some(b'n') => {
+                    self.expect_literal("null")?;
+                    Ok(Value::Null)
+                }
+                Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
+                Some(_) => Err(self.error("unexpected character")),
+                None => Err(self.error("unexpected end of input")),
+            }
+        }
+
+        fn parse_object(&mut self) -> Result<Value, ParseError> {
+            self.expect(b'{')?;
+            let mut map = Map::new();
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Value::Object(map));
+            }
+            loop {
+                self.skip_whitespace();
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(b':')?;
+                let value = self.parse_value()?;
+                map.insert(key, value);
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b',') => continue,
+                    Some(b'}') => break,
+                    _ => return Err(self.error("expected ',' or '}' in object")),
+                }
+            }
+            Ok(Value::Object(map))
+        }
+
+        fn parse_array(&mut self) -> Result<Value, ParseError> {
+            self.expect(b'[')?;
+            let mut items = Vec::new();
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            loop {
+                let value = self.parse_value()?;
+                items.push(value);
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(b',') => continue,
+                    Some(b']') => break,
+                    _ => return Err(self.error("expected ',' or ']' in array")),
+                }
+            }
+            Ok(Value::Array(items))
+        }
+
+        fn parse_string(&mut self) -> Result<String, ParseError> {
+            self.expect(b'"')?;
+            let mut out = String::new();
+            loop {
+                match self.bump() {
+                    Some(b'"') => break,
+                    Some(b'\\') => match self.bump() {
+                        Some(b'"') => out.push('"'),
+                        Some(b'\\') => out.push('\\'),
+                        Some(b'/') => out.push('/'),
+                        Some(b'n') => out.push('\n'),
+                        Some(b't') => out.push('\t'),
+                        Some(b'r') => out.push('\r'),
+                        Some(b'b') => out.push('\u{0008}'),
+                        Some(b'f') => out.push('\u{000C}'),
+                        Some(b'u') => {
+                            let code = self.parse_hex4()?;
+                            out.push(self.parse_unicode_escape(code)?);
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    },
+                    Some(b) => {
+                        // Re-decode as UTF-8 rather than byte-by-byte so
+                        // multi-byte characters survive the round-trip.
+                        let start = self.pos - 1;
+                        let rest = &self.bytes[start..];
+                        let s = str::from_utf8(rest).map_err(|_| self.error("invalid UTF-8 in string"))?;
+                        let ch = s.chars().next().ok_or_else(|| self.error("invalid UTF-8 in string"))?;
+                        out.push(ch);
+                        self.pos = start + ch.len_utf8();
+                        let _ = b;
+                    }
+                    None => return Err(self.error("unterminated string")),
+                }
+            }
+            Ok(out)
+        }
+
+        fn parse_hex4(&mut self) -> Result<u16, ParseError> {
+            let mut value: u16 = 0;
+            for _ in 0..4 {
+                let digit = self.bump().ok_or_else(|| self.error("unterminated \\u escape"))?;
+                let nibble = match digit {
+                    b'0'..=b'9' => digit - b'0',
+                    b'a'..=b'f' => digit - b'a' + 10,
+                    b'A'..=b'F' => digit - b'A' + 10,
+                    _ => return Err(self.error("invalid \\u escape")),
+                };
+                value = value * 16 + nibble as u16;
+            }
+            Ok(value)
+        }
+
+        /// Turns a `\uXXXX` code unit into a `char`, combining a high
+        /// surrogate with the low surrogate of a following `\uXXXX` escape
+        /// (JSON's encoding for astral-plane characters). A surrogate with
+        /// no valid partner is a parse error rather than silent corruption.
+        fn parse_unicode_escape(&mut self, code: u16) -> Result<char, ParseError> {
+            if !(0xD800..=0xDFFF).contains(&code) {
+                return char::from_u32(code as u32).ok_or_else(|| self.error("invalid \\u escape"));
+            }
+            if !(0xD800..=0xDBFF).contains(&code) {
+                return Err(self.error("unpaired low surrogate in \\u escape"));
+            }
+            if self.bump() != Some(b'\\') || self.bump() != Some(b'u') {
+                return Err(self.error("unpaired high surrogate in \\u escape"));
+            }
+            let low = self.parse_hex4()?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(self.error("high surrogate not followed by a low surrogate"));
+            }
+            let combined = 0x10000 + ((code as u32 - 0xD800) << 10) + (low as u32 - 0xDC00);
+            char::from_u32(combined).ok_or_else(|| self.error("invalid surrogate pair"))
+        }
+
+        fn parse_number(&mut self) -> Result<Value, ParseError> {
+            let start = self.pos;
+            if self.peek() == Some(b'-') {
+                self.pos += 1;
+            }
+            while matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.pos += 1;
+            }
+            if self.peek() == Some(b'.') {
+                self.pos += 1;
+                while matches!(self.peek(), Some(b'0'..=b'9')) {
+                    self.pos += 1;
+                }
+            }
+            if matches!(self.peek(), Some(b'e') | Some(b'E')) {
+                self.pos += 1;
+                if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+                    self.pos += 1;
+                }
+                while matches!(self.peek(), Some(b'0'..=b'9')) {
+                    self.pos += 1;
+                }
+            }
+            let text = str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+            text.parse::<f64>().map(Value::Number).map_err(|_| self.error("invalid number"))
+        }
+    }
+
+    pub fn to_string(value: &Value) -> String {
+        let mut out = String::new();
+        write_value(value, &mut out);
+        out
+    }
+
+    fn write_value(value: &Value, out: &mut String) {
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Bool(true) => out.push_str("true"),
+            Value::Bool(false) => out.push_str("false"),
+            Value::Number(n) => write_number(*n, out),
+            Value::String(s) => write_string(s, out),
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_value(item, out);
+                }
+                out.push(']');
+            }
+            Value::Object(map) => {
+                out.push('{');
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_string(key, out);
+                    out.push(':');
+                    write_value(value, out);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    fn write_number(n: f64, out: &mut String) {
+        if n.fract() == 0.0 && n.is_finite() && n.abs() < 1e15 {
+            out.push_str(&format!("{}", n as i64));
+        } else {
+            out.push_str(&format!("{}", n));
+        }
+    }
+
+    fn write_string(s: &str, out: &mut String) {
+        out.push('"');
+        for ch in s.chars() {
+            match ch {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '\r' => out.push_str("\\r"),
+                '\u{0008}' => out.push_str("\\b"),
+                '\u{000C}' => out.push_str("\\f"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn round_trips_a_nested_value() {
+            let input = r#"{"a":1,"b":[true,false,null],"c":{"d":"e\"f"}}"#;
+            let value = parse(input).unwrap();
+            assert_eq!(to_string(&value), input);
+        }
+
+        #[test]
+        fn combines_a_surrogate_pair_into_one_char() {
+            // \uD83D\uDE00 is the UTF-16 surrogate pair for U+1F600 (an
+            // astral-plane emoji), which must decode to a single char.
+            let value = parse(r#""\uD83D\uDE00""#).unwrap();
+            assert_eq!(value, Value::String("\u{1F600}".to_string()));
+        }
+
+        #[test]
+        fn rejects_an_unpaired_high_surrogate() {
+            assert!(parse(r#""\uD83D""#).is_err());
+        }
+
+        #[test]
+        fn rejects_an_unpaired_low_surrogate() {
+            assert!(parse(r#""\uDE00""#).is_err());
+        }
+    }
+}
+
+/// Named operations `handle` can dispatch to, keyed by the request's
+/// `"method"` field. Add an entry to `HANDLERS` to expose a new operation
+/// without touching the raw pointer ABI in `handle`.
+mod handlers {
+    use super::json;
+    use std::collections::HashMap;
+
+    type HandlerFn = fn(json::Value) -> json::Value;
+    type HandlerEntry = (&'static str, HandlerFn);
+
+    pub const HANDLERS: &[HandlerEntry] =
+        &[("echo", echo), ("word_count", word_count), ("csv_to_json", csv_to_json)];
+
+    fn echo(payload: json::Value) -> json::Value {
+        let mut obj = match payload {
+            json::Value::Object(obj) => obj,
+            _ => json::Map::new(),
+        };
+        obj.insert("message", json::Value::String("Hello from Rust WASM!".to_string()));
+        json::Value::Object(obj)
+    }
+
+    /// Takes `{"text": "..."}` and returns an object mapping each
+    /// whitespace-delimited token to its occurrence count, in first-seen
+    /// order.
+    fn word_count(payload: json::Value) -> json::Value {
+        let text = match &payload {
+            json::Value::Object(obj) => match obj.get("text") {
+                Some(json::Value::String(s)) => s.as_str(),
+                _ => "",
+            },
+            _ => "",
+        };
+
+        let mut counts: HashMap<&str, u64> = HashMap::new();
+        let mut order: Vec<&str> = Vec::new();
+        for word in text.split_whitespace() {
+            let count = counts.entry(word).or_insert(0);
+            if *count == 0 {
+                order.push(word);
+            }
+            *count += 1;
+        }
+
+        let mut result = json::Map::new();
+        for word in order {
+            result.insert(word, json::Value::Number(counts[word] as f64));
+        }
+        json::Value::Object(result)
+    }
+
+    /// Takes `{"csv": "...", "delimiter": ";"}` and returns
+    /// `{"records": [...], "skipped": [rowIndex, ...]}`: one object per
+    /// data row, keyed by the header row, with blank/all-empty rows
+    /// skipped and recorded rather than aborting the whole conversion.
+    fn csv_to_json(payload: json::Value) -> json::Value {
+        let obj = match payload {
+            json::Value::Object(obj) => obj,
+            _ => json::Map::new(),
+        };
+
+        let csv = match obj.get("csv") {
+            Some(json::Value::String(s)) => s.as_str(),
+            _ => "",
+        };
+        // A trailing newline is just an end-of-file marker, not a blank
+        // row, so strip it before splitting to avoid reporting a phantom
+        // skipped row for every CSV that ends in one.
+        let csv = csv.strip_suffix('\n').unwrap_or(csv);
+        let csv = csv.strip_suffix('\r').unwrap_or(csv);
+        let delimiter = match obj.get("delimiter") {
+            Some(json::Value::String(s)) => s.chars().next().unwrap_or(','),
+            _ => ',',
+        };
+
+        let mut headers: Option<Vec<String>> = None;
+        let mut records: Vec<json::Value> = Vec::new();
+        let mut skipped: Vec<json::Value> = Vec::new();
+        let mut row_index: u64 = 0;
+
+        for line in csv.split('\n') {
+            let line = line.trim_end_matches('\r');
+
+            let fields: Vec<&str> = line.split(delimiter).collect();
+            let all_empty = fields.iter().all(|field| field.trim().is_empty());
+
+            if headers.is_none() {
+                if all_empty {
+                    skipped.push(json::Value::Number(row_index as f64));
+                    row_index += 1;
+                    continue;
+                }
+                headers = Some(fields.iter().map(|field| field.to_string()).collect());
+                continue;
+            }
+            let headers = headers.as_ref().unwrap();
+
+            if all_empty {
+                skipped.push(json::Value::Number(row_index as f64));
+                row_index += 1;
+                continue;
+            }
+
+            let mut record = json::Map::new();
+            for (i, header) in headers.iter().enumerate() {
+                let value = fields.get(i).copied().unwrap_or("");
+                record.insert(header.clone(), json::Value::String(value.to_string()));
+            }
+            records.push(json::Value::Object(record));
+            row_index += 1;
+        }
+
+        let mut result = json::Map::new();
+        result.insert("records", json::Value::Array(records));
+        result.insert("skipped", json::Value::Array(skipped));
+        json::Value::Object(result)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn counts_words_in_first_seen_order() {
+            let mut text = json::Map::new();
+            text.insert("text", json::Value::String("This is is".to_string()));
+            let result = word_count(json::Value::Object(text));
+            assert_eq!(result, json::parse(r#"{"This":1,"is":2}"#).unwrap());
+        }
+
+        #[test]
+        fn word_count_ignores_a_missing_text_field() {
+            let result = word_count(json::Value::Object(json::Map::new()));
+            assert_eq!(result, json::Value::Object(json::Map::new()));
+        }
+
+        fn payload(csv: &str, delimiter: &str) -> json::Value {
+            let mut obj = json::Map::new();
+            obj.insert("csv", json::Value::String(csv.to_string()));
+            obj.insert("delimiter", json::Value::String(delimiter.to_string()));
+            json::Value::Object(obj)
+        }
+
+        #[test]
+        fn skips_a_trailing_blank_row() {
+            let result = csv_to_json(payload("a;b\n1;2\n;;", ";"));
+            assert_eq!(
+                result,
+                json::parse(r#"{"records":[{"a":"1","b":"2"}],"skipped":[1]}"#).unwrap()
+            );
+        }
+
+        #[test]
+        fn skips_an_interspersed_blank_row() {
+            let result = csv_to_json(payload("a;b\n1;2\n;;\n3;4", ";"));
+            assert_eq!(
+                result,
+                json::parse(
+                    r#"{"records":[{"a":"1","b":"2"},{"a":"3","b":"4"}],"skipped":[1]}"#
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn skips_a_blank_row_before_the_header() {
+            let result = csv_to_json(payload(";;;\na;b\n1;2", ";"));
+            assert_eq!(
+                result,
+                json::parse(r#"{"records":[{"a":"1","b":"2"}],"skipped":[0]}"#).unwrap()
+            );
+        }
+    }
 }